@@ -0,0 +1,265 @@
+//! Async mirror of [`crate::Eeprom`] built on `embedded-hal-async`, for running on
+//! Embassy-style executors without blocking. Gated behind the `async` feature; the blocking
+//! driver in the crate root remains the default.
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+use crate::{Config, Dest, Error, IdentificationPage, NoIdentificationPage};
+
+/// Async driver for the M24Cxx / AT24 family of I2C EEPROMs
+///
+/// Mirrors [`crate::Eeprom`] one-to-one; see its docs for what `PAGE_SIZE`, `CAPACITY` and
+/// `ADDRESS_BYTES` mean.
+pub struct Eeprom<I2C, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8> {
+    _device_family: F,
+    config: Config,
+    i2c: I2C,
+    cmd_buf: [u8; 66],
+}
+
+/// Async M24C64 driver: 256 pages of 32 bytes, 2-byte addressing
+pub type M24C64<I2C, F> = Eeprom<I2C, F, 32, { 256 * 32 }, 2>;
+
+impl<I2C, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: I2c,
+{
+    /// Create a new instance of the driver
+    pub fn new(
+        i2c: I2C,
+        config: Config,
+    ) -> Eeprom<I2C, NoIdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES> {
+        Eeprom {
+            _device_family: NoIdentificationPage,
+            config,
+            i2c,
+            cmd_buf: [0; 66],
+        }
+    }
+
+    /// Create an instance of the M24C64-D device family type
+    ///
+    /// See [`crate::Eeprom::with_id_page`] for details on the Identification Page.
+    pub fn with_id_page(self) -> Eeprom<I2C, IdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES> {
+        Eeprom {
+            _device_family: IdentificationPage,
+            config: self.config,
+            i2c: self.i2c,
+            cmd_buf: self.cmd_buf,
+        }
+    }
+
+    // Warning! Does not check for page wraps
+    async fn write_raw(
+        &mut self,
+        dest: Dest,
+        address: usize,
+        bytes: &[u8],
+    ) -> Result<(), Error<I2C::Error>> {
+        let (addr_len, device_addr) = if ADDRESS_BYTES == 2 {
+            self.cmd_buf[0] = (address >> 8) as u8;
+            self.cmd_buf[1] = (address & 0xff) as u8;
+            (2, self.config.address | dest as u8)
+        } else {
+            self.cmd_buf[0] = (address & 0xff) as u8;
+            // Shift the folded high address bits clear of `dest`'s and `config.address`'s
+            // fixed bits (bits 0-3) so they can't alias a different memory location.
+            (1, self.config.address | dest as u8 | (((address >> 8) as u8) << 4))
+        };
+        self.cmd_buf[addr_len..bytes.len() + addr_len].copy_from_slice(bytes);
+        self.i2c
+            .write(device_addr, &self.cmd_buf[0..bytes.len() + addr_len])
+            .await
+            .map_err(Error::I2C)
+    }
+
+    async fn read_raw(
+        &mut self,
+        dest: Dest,
+        address: usize,
+        bytes: &mut [u8],
+    ) -> Result<(), Error<I2C::Error>> {
+        let (addr_len, device_addr) = if ADDRESS_BYTES == 2 {
+            self.cmd_buf[0] = (address >> 8) as u8;
+            self.cmd_buf[1] = (address & 0xff) as u8;
+            (2, self.config.address | dest as u8)
+        } else {
+            self.cmd_buf[0] = (address & 0xff) as u8;
+            // Shift the folded high address bits clear of `dest`'s and `config.address`'s
+            // fixed bits (bits 0-3) so they can't alias a different memory location.
+            (1, self.config.address | dest as u8 | (((address >> 8) as u8) << 4))
+        };
+        self.i2c
+            .write_read(device_addr, &self.cmd_buf[0..addr_len], bytes)
+            .await
+            .map_err(Error::I2C)
+    }
+
+    /// Write exactly one page
+    pub async fn write_page(
+        &mut self,
+        page: u8,
+        bytes: &[u8; PAGE_SIZE],
+    ) -> Result<(), Error<I2C::Error>> {
+        self.write_raw(Dest::Memory, page as usize * PAGE_SIZE, bytes).await
+    }
+
+    /// Write bytes to an arbitrary location in memory
+    ///
+    /// Note: Checks whether buffer will fit on page and will **not** wrap
+    pub async fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), Error<I2C::Error>> {
+        let start_idx = address % PAGE_SIZE;
+        if start_idx + bytes.len() > PAGE_SIZE {
+            return Err(Error::Address);
+        }
+        self.write_raw(Dest::Memory, address, bytes).await
+    }
+
+    /// Write a single byte to an arbitrary location in memory
+    pub async fn write_byte(&mut self, address: usize, data: u8) -> Result<(), Error<I2C::Error>> {
+        self.write(address, &[data]).await
+    }
+
+    /// Write bytes to an arbitrary location in memory, automatically splitting the buffer at
+    /// each page boundary
+    ///
+    /// See [`crate::Eeprom::write_all`] for the chunking behaviour; here the write-cycle wait
+    /// after each chunk is a `delay.delay_ms(..).await` rather than a blocking spin.
+    pub async fn write_all<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        address: usize,
+        bytes: &[u8],
+    ) -> Result<(), Error<I2C::Error>> {
+        if address + bytes.len() > CAPACITY {
+            return Err(Error::Address);
+        }
+
+        let first_chunk = (PAGE_SIZE - (address % PAGE_SIZE)).min(bytes.len());
+        let (head, tail) = bytes.split_at(first_chunk);
+        self.write_raw(Dest::Memory, address, head).await?;
+        self.poll_ready(delay, 1, 20).await?;
+
+        let mut address = address + first_chunk;
+        for chunk in tail.chunks(PAGE_SIZE) {
+            self.write_raw(Dest::Memory, address, chunk).await?;
+            self.poll_ready(delay, 1, 20).await?;
+            address += chunk.len();
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the device's internal write cycle to finish
+    ///
+    /// See [`crate::Eeprom::poll_ready`]; the wait between attempts is an async delay instead
+    /// of a blocking one.
+    pub async fn poll_ready<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        delay_ms: u32,
+        max_attempts: u32,
+    ) -> Result<(), Error<I2C::Error>> {
+        for _ in 0..max_attempts {
+            if self
+                .i2c
+                .write(self.config.address | Dest::Memory as u8, &[])
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+            delay.delay_ms(delay_ms).await;
+        }
+        Err(Error::Conn)
+    }
+
+    /// Read exactly one page into a buffer
+    pub async fn read_page(
+        &mut self,
+        page: u8,
+        bytes: &mut [u8; PAGE_SIZE],
+    ) -> Result<(), Error<I2C::Error>> {
+        self.read_raw(Dest::Memory, page as usize * PAGE_SIZE, bytes).await
+    }
+
+    /// Read a memory location into a buffer until it is full
+    ///
+    /// Note: Checks whether address is out of bounds and will **not** wrap
+    pub async fn read(&mut self, address: usize, bytes: &mut [u8]) -> Result<(), Error<I2C::Error>> {
+        if address + bytes.len() > CAPACITY {
+            return Err(Error::Address);
+        }
+        self.read_raw(Dest::Memory, address, bytes).await
+    }
+
+    /// Read a single byte from an arbitrary location in memory
+    pub async fn read_byte(&mut self, address: usize) -> Result<u8, Error<I2C::Error>> {
+        let mut byte = [0u8];
+        self.read(address, &mut byte).await?;
+        Ok(byte[0])
+    }
+
+    /// Read the byte following the last accessed location using the device's internal address
+    /// counter
+    ///
+    /// See [`crate::Eeprom::read_current_address`]. `embedded-hal-async`'s `I2c` trait already
+    /// covers a bare read, so unlike the blocking driver this needs no additional bound.
+    pub async fn read_current_address(&mut self) -> Result<u8, Error<I2C::Error>> {
+        let mut byte = [0u8];
+        self.i2c
+            .read(self.config.address | Dest::Memory as u8, &mut byte)
+            .await
+            .map_err(Error::I2C)?;
+        Ok(byte[0])
+    }
+}
+
+impl<I2C, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    Eeprom<I2C, IdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: I2c,
+{
+    /// Write bytes to an arbitrary location on the Identification page
+    ///
+    /// Note: Checks whether buffer will fit on page and will **not** wrap
+    pub async fn write_id(&mut self, mut address: usize, bytes: &[u8]) -> Result<(), Error<I2C::Error>> {
+        if address + bytes.len() > 32 {
+            return Err(Error::Address);
+        }
+        // Unset address bit 10
+        address &= !(1 << 10);
+        self.write_raw(Dest::Identification, address, bytes).await
+    }
+
+    /// Write exactly 32 bytes to the Identification page
+    pub async fn write_id_page(&mut self, bytes: &[u8; 32]) -> Result<(), Error<I2C::Error>> {
+        self.write_raw(Dest::Identification, 0, bytes).await
+    }
+
+    /// Permanently locs the Identification page (this makes it read-only)
+    pub async fn lock_id_page(&mut self) -> Result<(), Error<I2C::Error>> {
+        // Set address bit 10 to `1`
+        let address = 0x400;
+        // Set data bit 2 to `1`;
+        let data_byte = 0x2;
+        self.write_raw(Dest::Identification, address, &[data_byte]).await
+    }
+
+    /// Read a location on the Identification page into a buffer until it is full
+    ///
+    /// Note: Checks whether address is out of bounds and will **not** wrap
+    pub async fn read_id(&mut self, address: usize, bytes: &mut [u8]) -> Result<(), Error<I2C::Error>> {
+        if address + bytes.len() > 32 {
+            return Err(Error::Address);
+        }
+        self.read_raw(Dest::Identification, address, bytes).await
+    }
+
+    /// Read the whole Identification page into a buffer
+    pub async fn read_id_page(&mut self, bytes: &mut [u8; 32]) -> Result<(), Error<I2C::Error>> {
+        self.read_raw(Dest::Identification, 0, bytes).await
+    }
+}
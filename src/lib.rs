@@ -1,6 +1,13 @@
 #![cfg_attr(not(test), no_std)]
 
-use embedded_hal::blocking::i2c::{Write, WriteRead};
+mod nor_flash;
+
+/// Async driver variant built on `embedded-hal-async`, gated behind the `async` feature
+#[cfg(feature = "async")]
+pub mod asynch;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
 
 /// 256 pages containing 32 bytes
 pub const ADDRESS_LAST: usize = 256 * 32;
@@ -20,6 +27,8 @@ pub enum Error<I> {
     Address,
     /// Port error (invalid or out of bounds)
     Port,
+    /// Alignment error (offset or length not aligned to the required size)
+    Alignment,
 }
 
 #[repr(u8)]
@@ -54,27 +63,46 @@ pub struct Config {
     pub address: u8,
 }
 
-/// M24C64 driver
-pub struct M24C64<I2C, F> {
+/// Generic driver for the M24Cxx / AT24 family of I2C EEPROMs
+///
+/// `PAGE_SIZE`, `CAPACITY` and `ADDRESS_BYTES` describe the geometry of a specific part:
+///
+/// * `PAGE_SIZE` - size in bytes of a single page write
+/// * `CAPACITY` - total number of bytes in the main memory array
+/// * `ADDRESS_BYTES` - number of address bytes sent over I2C (`1` or `2`)
+///
+/// For parts that address with a single byte, the bits of the memory address that don't fit are
+/// folded into the I2C device address, shifted up to start at bit 4 so they stay clear of the
+/// fixed bits (0-3) taken by the memory/Identification selector and `Config::address`. They are
+/// not placed in the low, chip-enable bit positions.
+///
+/// `M24C64` is a type alias presetting these parameters for the M24C64; most users should reach
+/// for that rather than naming `Eeprom` directly.
+pub struct Eeprom<I2C, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8> {
     // Device family. M24C64 (no Identification page) or M24C64D (with Identification page)
     _device_family: F,
     /// Device configuration
     config: Config,
     /// `embedded-hal` compatible I2C instance
     i2c: I2C,
-    /// Command buffer
-    cmd_buf: [u8; 34],
+    // Large enough for the widest page (64 bytes) plus two address bytes, across every part
+    // this driver is parameterized for.
+    cmd_buf: [u8; 66],
 }
 
-impl<I2C, S, F> M24C64<I2C, F>
+/// M24C64 driver: 256 pages of 32 bytes, 2-byte addressing
+pub type M24C64<I2C, F> = Eeprom<I2C, F, 32, { 256 * 32 }, 2>;
+
+impl<I2C, S, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
 where
     I2C: Write<u8, Error = S> + WriteRead<u8, Error = S>,
 {
-    /// Create a new instance of the M24C64 driver
+    /// Create a new instance of the driver
     /// # Arguments
     ///
     /// * `i2c` - embedded-hal compatible I2C instance
-    /// * `config` - The M24C64 `Config` device configuration struct
+    /// * `config` - The `Config` device configuration struct
     ///
     /// # Example
     ///
@@ -83,12 +111,15 @@ where
     ///
     /// let eeprom = M24C64::new(i2c, Config::default());
     /// ```
-    pub fn new(i2c: I2C, config: Config) -> M24C64<I2C, NoIdentificationPage> {
-        M24C64 {
+    pub fn new(
+        i2c: I2C,
+        config: Config,
+    ) -> Eeprom<I2C, NoIdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES> {
+        Eeprom {
             _device_family: NoIdentificationPage,
             config,
             i2c,
-            cmd_buf: [0; 34],
+            cmd_buf: [0; 66],
         }
     }
 
@@ -105,8 +136,8 @@ where
     ///
     /// let eeprom = M24C64::new(i2c, Config::default()).with_id_page();
     /// ```
-    pub fn with_id_page(self) -> M24C64<I2C, IdentificationPage> {
-        M24C64 {
+    pub fn with_id_page(self) -> Eeprom<I2C, IdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES> {
+        Eeprom {
             _device_family: IdentificationPage,
             config: self.config,
             i2c: self.i2c,
@@ -116,58 +147,194 @@ where
 
     // Warning! Does not check for page wraps
     fn write_raw(&mut self, dest: Dest, address: usize, bytes: &[u8]) -> Result<(), Error<S>> {
-        self.cmd_buf[0] = (address >> 8) as u8;
-        self.cmd_buf[1] = (address & 0xff) as u8;
-        self.cmd_buf[2..bytes.len() + 2].copy_from_slice(bytes);
+        let (addr_len, device_addr) = if ADDRESS_BYTES == 2 {
+            self.cmd_buf[0] = (address >> 8) as u8;
+            self.cmd_buf[1] = (address & 0xff) as u8;
+            (2, self.config.address | dest as u8)
+        } else {
+            self.cmd_buf[0] = (address & 0xff) as u8;
+            // Shift the folded high address bits clear of `dest`'s and `config.address`'s
+            // fixed bits (bits 0-3) so they can't alias a different memory location.
+            (1, self.config.address | dest as u8 | (((address >> 8) as u8) << 4))
+        };
+        self.cmd_buf[addr_len..bytes.len() + addr_len].copy_from_slice(bytes);
         self.i2c
-            .write(
-                self.config.address | dest as u8,
-                &self.cmd_buf[0..bytes.len() + 2],
-            )
+            .write(device_addr, &self.cmd_buf[0..bytes.len() + addr_len])
             .map_err(Error::I2C)
     }
 
     fn read_raw(&mut self, dest: Dest, address: usize, bytes: &mut [u8]) -> Result<(), Error<S>> {
-        self.cmd_buf[0] = (address >> 8) as u8;
-        self.cmd_buf[1] = (address & 0xff) as u8;
+        let (addr_len, device_addr) = if ADDRESS_BYTES == 2 {
+            self.cmd_buf[0] = (address >> 8) as u8;
+            self.cmd_buf[1] = (address & 0xff) as u8;
+            (2, self.config.address | dest as u8)
+        } else {
+            self.cmd_buf[0] = (address & 0xff) as u8;
+            // Shift the folded high address bits clear of `dest`'s and `config.address`'s
+            // fixed bits (bits 0-3) so they can't alias a different memory location.
+            (1, self.config.address | dest as u8 | (((address >> 8) as u8) << 4))
+        };
         self.i2c
-            .write_read(self.config.address | dest as u8, &self.cmd_buf[0..2], bytes)
+            .write_read(device_addr, &self.cmd_buf[0..addr_len], bytes)
             .map_err(Error::I2C)
     }
 
-    /// Write exactly 32 bytes to a page
-    pub fn write_page(&mut self, page: u8, bytes: &[u8; 32]) -> Result<(), Error<S>> {
-        self.write_raw(Dest::Memory, (page * 32) as usize, bytes)
+    /// Write exactly one page
+    pub fn write_page(&mut self, page: u8, bytes: &[u8; PAGE_SIZE]) -> Result<(), Error<S>> {
+        self.write_raw(Dest::Memory, page as usize * PAGE_SIZE, bytes)
     }
 
     /// Write bytes to an arbitrary location in memory
     ///
     /// Note: Checks whether buffer will fit on page and will **not** wrap
     pub fn write(&mut self, address: usize, bytes: &[u8]) -> Result<(), Error<S>> {
-        let start_idx = address % 32;
-        if start_idx + bytes.len() > 32 {
+        let start_idx = address % PAGE_SIZE;
+        if start_idx + bytes.len() > PAGE_SIZE {
             return Err(Error::Address);
         }
         self.write_raw(Dest::Memory, address, bytes)
     }
 
+    /// Write a single byte to an arbitrary location in memory
+    pub fn write_byte(&mut self, address: usize, data: u8) -> Result<(), Error<S>> {
+        self.write(address, &[data])
+    }
+
+    /// Write bytes to an arbitrary location in memory, automatically splitting the buffer at
+    /// each page boundary
+    ///
+    /// Unlike [`Self::write`], the buffer is allowed to span any number of pages: `bytes` is
+    /// split into a first, possibly short, chunk that fills the remainder of the starting page,
+    /// followed by as many aligned page writes as needed and a final, possibly short, chunk.
+    /// Because each chunk triggers an internally-timed write cycle on the device,
+    /// [`Self::poll_ready`] is used to wait for it to finish after every chunk, including the
+    /// last, so the device is always ready again by the time this returns.
+    ///
+    /// Note: Checks whether the buffer is out of bounds and will **not** wrap at the end of the
+    /// array
+    pub fn write_all<D: DelayMs<u32>>(
+        &mut self,
+        delay: &mut D,
+        address: usize,
+        bytes: &[u8],
+    ) -> Result<(), Error<S>> {
+        if address + bytes.len() > CAPACITY {
+            return Err(Error::Address);
+        }
+        self.write_chunked(address, bytes, |s| s.poll_ready(delay, 1, 20))
+    }
+
+    // Shared by `write_all` and the `NorFlash` impl: splits `bytes` at each page boundary,
+    // writing a page at a time and calling `wait` after every chunk, including the last, so
+    // the device's internal write cycle has always finished by the time this returns.
+    fn write_chunked(
+        &mut self,
+        address: usize,
+        bytes: &[u8],
+        mut wait: impl FnMut(&mut Self) -> Result<(), Error<S>>,
+    ) -> Result<(), Error<S>> {
+        let first_chunk = (PAGE_SIZE - (address % PAGE_SIZE)).min(bytes.len());
+        let (head, tail) = bytes.split_at(first_chunk);
+        self.write_raw(Dest::Memory, address, head)?;
+        wait(self)?;
+
+        let mut address = address + first_chunk;
+        for chunk in tail.chunks(PAGE_SIZE) {
+            self.write_raw(Dest::Memory, address, chunk)?;
+            wait(self)?;
+            address += chunk.len();
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the device's internal write cycle to finish
+    ///
+    /// After a byte or page write the device NAKs its own address until the write cycle
+    /// (typically ~5 ms) completes. This repeatedly issues a zero-length write to the device
+    /// address, waiting `delay_ms` milliseconds between attempts, and returns as soon as the
+    /// device ACKs. Returns `Err(Error::Conn)` if it still hasn't acknowledged after
+    /// `max_attempts` tries.
+    pub fn poll_ready<D: DelayMs<u32>>(
+        &mut self,
+        delay: &mut D,
+        delay_ms: u32,
+        max_attempts: u32,
+    ) -> Result<(), Error<S>> {
+        for _ in 0..max_attempts {
+            if self
+                .i2c
+                .write(self.config.address | Dest::Memory as u8, &[])
+                .is_ok()
+            {
+                return Ok(());
+            }
+            delay.delay_ms(delay_ms);
+        }
+        Err(Error::Conn)
+    }
+
+    // Like `poll_ready`, but without a delay to wait on, for callers (such as the
+    // `NorFlash` impl) that only have a fixed trait signature to work with.
+    fn poll_ready_spin(&mut self) -> Result<(), Error<S>> {
+        const MAX_ATTEMPTS: u32 = 1_000_000;
+        for _ in 0..MAX_ATTEMPTS {
+            if self
+                .i2c
+                .write(self.config.address | Dest::Memory as u8, &[])
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+        Err(Error::Conn)
+    }
+
     /// Read exactly one page into a buffer
-    pub fn read_page(&mut self, page: u8, bytes: &mut [u8; 32]) -> Result<(), Error<S>> {
-        self.read_raw(Dest::Memory, (page * 32) as usize, bytes)
+    pub fn read_page(&mut self, page: u8, bytes: &mut [u8; PAGE_SIZE]) -> Result<(), Error<S>> {
+        self.read_raw(Dest::Memory, page as usize * PAGE_SIZE, bytes)
     }
 
     /// Read a memory location into a buffer until it is full
     ///
     /// Note: Checks whether address is out of bounds and will **not** wrap
     pub fn read(&mut self, address: usize, bytes: &mut [u8]) -> Result<(), Error<S>> {
-        if address + bytes.len() > ADDRESS_LAST {
+        if address + bytes.len() > CAPACITY {
             return Err(Error::Address);
         }
         self.read_raw(Dest::Memory, address, bytes)
     }
+
+    /// Read a single byte from an arbitrary location in memory
+    pub fn read_byte(&mut self, address: usize) -> Result<u8, Error<S>> {
+        let mut byte = [0u8];
+        self.read(address, &mut byte)?;
+        Ok(byte[0])
+    }
+}
+
+impl<I2C, S, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: Write<u8, Error = S> + WriteRead<u8, Error = S> + Read<u8, Error = S>,
+{
+    /// Read the byte following the last accessed location using the device's internal address
+    /// counter
+    ///
+    /// Unlike [`Self::read`], this performs a bare I2C read with no address phase, so it picks
+    /// up wherever the last `read`, `write` or page operation left off (and wraps to the start
+    /// of the page/array past the end, per the datasheet).
+    pub fn read_current_address(&mut self) -> Result<u8, Error<S>> {
+        let mut byte = [0u8];
+        self.i2c
+            .read(self.config.address | Dest::Memory as u8, &mut byte)
+            .map_err(Error::I2C)?;
+        Ok(byte[0])
+    }
 }
 
-impl<I2C, S> M24C64<I2C, IdentificationPage>
+impl<I2C, S, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    Eeprom<I2C, IdentificationPage, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
 where
     I2C: Write<u8, Error = S> + WriteRead<u8, Error = S>,
 {
@@ -212,3 +379,117 @@ where
         self.read_raw(Dest::Identification, 0, bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct MockI2c {
+        // (device address, bytes sent) for every `write`/`write_read` call, in order
+        writes: Vec<(u8, Vec<u8>)>,
+    }
+
+    impl Write<u8> for MockI2c {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+            self.writes.push((address, bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    impl WriteRead<u8> for MockI2c {
+        type Error = ();
+
+        fn write_read(
+            &mut self,
+            address: u8,
+            bytes: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            self.writes.push((address, bytes.to_vec()));
+            buffer.fill(0);
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayMs<u32> for NoDelay {
+        fn delay_ms(&mut self, _ms: u32) {}
+    }
+
+    // Only the non-empty writes carry an address/data payload; `poll_ready`/`poll_ready_spin`
+    // show up as zero-length writes and aren't what these tests are checking.
+    fn payload_writes(writes: &[(u8, Vec<u8>)]) -> Vec<&(u8, Vec<u8>)> {
+        writes.iter().filter(|(_, bytes)| !bytes.is_empty()).collect()
+    }
+
+    #[test]
+    fn write_chunked_splits_unaligned_multi_page_write() {
+        let mut eeprom =
+            Eeprom::<MockI2c, NoIdentificationPage, 32, { 256 * 32 }, 2>::new(MockI2c::default(), Config::default());
+        let mut delay = NoDelay;
+
+        // Starting 16 bytes into page 0 with 40 bytes should split into a 16-byte remainder of
+        // page 0, then a 24-byte write starting at page 1.
+        eeprom.write_all(&mut delay, 16, &[0xAA; 40]).unwrap();
+
+        let writes = payload_writes(&eeprom.i2c.writes);
+        assert_eq!(writes.len(), 2);
+
+        assert_eq!(&writes[0].1[0..2], &[0, 16]);
+        assert_eq!(writes[0].1.len(), 2 + 16);
+
+        assert_eq!(&writes[1].1[0..2], &[0, 32]);
+        assert_eq!(writes[1].1.len(), 2 + 24);
+    }
+
+    #[test]
+    fn write_chunked_buffer_exactly_filling_one_page_is_not_split() {
+        let mut eeprom =
+            Eeprom::<MockI2c, NoIdentificationPage, 32, { 256 * 32 }, 2>::new(MockI2c::default(), Config::default());
+        let mut delay = NoDelay;
+
+        eeprom.write_all(&mut delay, 0, &[0xBB; 32]).unwrap();
+
+        let writes = payload_writes(&eeprom.i2c.writes);
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].1.len(), 2 + 32);
+    }
+
+    #[test]
+    fn write_chunked_polls_ready_after_the_final_chunk() {
+        let mut eeprom =
+            Eeprom::<MockI2c, NoIdentificationPage, 32, { 256 * 32 }, 2>::new(MockI2c::default(), Config::default());
+        let mut delay = NoDelay;
+
+        eeprom.write_all(&mut delay, 16, &[0xAA; 40]).unwrap();
+
+        // One poll (zero-length write) after each of the two page writes, including the last.
+        let polls = eeprom.i2c.writes.iter().filter(|(_, bytes)| bytes.is_empty()).count();
+        assert_eq!(polls, 2);
+    }
+
+    #[test]
+    fn address_folding_packs_high_bits_into_unused_device_address_bits() {
+        // A smaller, single-address-byte part (8-byte pages, 2 KiB capacity, à la AT24C16),
+        // whose high address bits have to be folded into the I2C device address byte.
+        let mut eeprom = Eeprom::<MockI2c, NoIdentificationPage, 8, 2048, 1>::new(
+            MockI2c::default(),
+            Config::default(),
+        );
+
+        // Address 0x301 needs 3 extra bits (0x301 >> 8 == 0b011) folded above `Dest::Memory`'s
+        // fixed bits (0..=3), landing at bits 4..=6 of the device address.
+        eeprom.write(0x301, &[0x7f]).unwrap();
+
+        let writes = payload_writes(&eeprom.i2c.writes);
+        assert_eq!(writes.len(), 1);
+        let (device_addr, bytes) = writes[0];
+        assert_eq!(*device_addr, 0xa | (0b011 << 4));
+        assert_eq!(bytes, &[0x01, 0x7f]);
+    }
+}
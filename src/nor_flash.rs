@@ -0,0 +1,81 @@
+//! `embedded-storage` trait impls for the main memory array, so an `Eeprom` (e.g. `M24C64`) can
+//! be dropped into filesystem/key-value layers built on top of `ReadNorFlash`/`NorFlash`.
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash};
+
+use crate::{Dest, Eeprom, Error};
+
+impl<S> NorFlashError for Error<S>
+where
+    S: core::fmt::Debug,
+{
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            Error::Address => NorFlashErrorKind::OutOfBounds,
+            Error::Alignment => NorFlashErrorKind::NotAligned,
+            _ => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<I2C, S, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8> ErrorType
+    for Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: Write<u8, Error = S> + WriteRead<u8, Error = S>,
+    S: core::fmt::Debug,
+{
+    type Error = Error<S>;
+}
+
+impl<I2C, S, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8>
+    ReadNorFlash for Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: Write<u8, Error = S> + WriteRead<u8, Error = S>,
+    S: core::fmt::Debug,
+{
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Eeprom::read(self, offset as usize, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        CAPACITY
+    }
+}
+
+impl<I2C, S, F, const PAGE_SIZE: usize, const CAPACITY: usize, const ADDRESS_BYTES: u8> NorFlash
+    for Eeprom<I2C, F, PAGE_SIZE, CAPACITY, ADDRESS_BYTES>
+where
+    I2C: Write<u8, Error = S> + WriteRead<u8, Error = S>,
+    S: core::fmt::Debug,
+{
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = PAGE_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if !(from as usize).is_multiple_of(PAGE_SIZE) || !(to as usize).is_multiple_of(PAGE_SIZE) {
+            return Err(Error::Alignment);
+        }
+        if to as usize > CAPACITY {
+            return Err(Error::Address);
+        }
+
+        let blank = [0xffu8; 64];
+        let mut address = from as usize;
+        while address < to as usize {
+            self.write_raw(Dest::Memory, address, &blank[..PAGE_SIZE])?;
+            self.poll_ready_spin()?;
+            address += PAGE_SIZE;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        if offset as usize + bytes.len() > CAPACITY {
+            return Err(Error::Address);
+        }
+        self.write_chunked(offset as usize, bytes, |s| s.poll_ready_spin())
+    }
+}